@@ -0,0 +1,154 @@
+use std::path::PathBuf;
+
+use clap::{Args, Parser, Subcommand};
+
+use crate::SortMode;
+pub use crate::SwitchType;
+
+#[derive(Parser, Debug)]
+#[command(author, version, about = "A CLI/GUI that allows switching between windows in Hyprland", long_about = None)]
+pub struct App {
+    #[command(subcommand)]
+    pub command: Command,
+
+    #[command(flatten)]
+    pub global_opts: GlobalOpts,
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct GlobalOpts {
+    /// Don't actually dispatch any commands to Hyprland, just log what would happen
+    #[arg(long)]
+    pub dry_run: bool,
+
+    /// Increase log verbosity (-v, -vv, -vvv)
+    #[arg(short, long, action = clap::ArgAction::Count)]
+    pub verbose: u8,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// Initialize and start the Daemon
+    Init {
+        #[command(flatten)]
+        gui_conf: GuiConf,
+        /// Write a commented config.toml with the built-in defaults (if one doesn't exist yet) and exit
+        #[arg(long)]
+        write_default_config: bool,
+    },
+    /// Close the GUI, executes the command to switch window
+    Close {
+        /// Also terminate the daemon process after hiding the GUI
+        #[arg(long)]
+        kill: bool,
+    },
+    /// Used to send commands to the daemon (used in keymap that gets generated by gui)
+    Dispatch {
+        #[command(flatten)]
+        simple_opts: SimpleOpts,
+        /// Jump directly to the oldest outstanding urgent window instead of cycling
+        #[arg(long)]
+        to_urgent: bool,
+    },
+    /// Opens the GUI
+    Gui {
+        #[command(flatten)]
+        gui_conf: GuiConf,
+        #[command(flatten)]
+        simple_config: SimpleConf,
+    },
+    /// Switch without using the GUI / Daemon (switches directly)
+    Simple {
+        #[command(flatten)]
+        simple_opts: SimpleOpts,
+        #[command(flatten)]
+        simple_conf: SimpleConf,
+    },
+    /// Pipe the window list to an external launcher (rofi/dmenu/fzf) instead of the GUI
+    Menu {
+        #[command(flatten)]
+        simple_conf: SimpleConf,
+        #[command(flatten)]
+        gui_conf: GuiConf,
+        /// Launcher command reading "index\t<formatted line>" on stdin and printing the chosen
+        /// line on stdout, e.g. "rofi -dmenu -i"
+        #[arg(long, default_value = "rofi -dmenu -i")]
+        launcher: String,
+    },
+    /// Debug command to see how icons get resolved for a client class
+    Icon {
+        #[arg(default_value = "")]
+        class: String,
+        #[arg(long)]
+        desktop_files: bool,
+        #[arg(long)]
+        list: bool,
+        /// Extra directories searched for an icon file named after the class, before falling
+        /// back to the GTK icon theme
+        #[arg(long, value_delimiter = ',')]
+        icon_dirs: Vec<PathBuf>,
+        /// Icon name used when neither the theme nor a desktop file yields one
+        #[arg(long)]
+        fallback_icon: Option<String>,
+    },
+}
+
+#[derive(Args, Debug, Clone, Copy)]
+pub struct SimpleOpts {
+    /// Cycle backwards instead of forwards
+    #[arg(long)]
+    pub reverse: bool,
+    /// How many steps to advance (usually 1)
+    #[arg(long, default_value_t = 1)]
+    pub offset: u8,
+    /// Prefer cycling through currently-urgent clients before the normal order
+    #[arg(long)]
+    pub urgent_first: bool,
+}
+
+/// Unset fields fall back to `config.toml`, then to built-in defaults (see `crate::config`)
+#[derive(Args, Debug, Clone, Copy, Default)]
+pub struct SimpleConf {
+    /// What to cycle through
+    #[arg(long, value_enum)]
+    pub switch_type: Option<SwitchType>,
+    /// Ordering used to cycle clients
+    #[arg(long, value_enum)]
+    pub sort_mode: Option<SortMode>,
+    #[arg(long)]
+    pub filter_same_class: bool,
+    #[arg(long)]
+    pub filter_current_workspace: bool,
+    #[arg(long)]
+    pub filter_current_monitor: bool,
+    #[arg(long)]
+    pub ignore_workspaces: bool,
+    #[arg(long)]
+    pub ignore_monitors: bool,
+}
+
+/// Unset fields fall back to `config.toml`, then to built-in defaults (see `crate::config`)
+#[derive(Args, Debug, Clone, Default)]
+pub struct GuiConf {
+    #[arg(long)]
+    pub custom_css: Option<PathBuf>,
+    #[arg(long)]
+    pub show_title: bool,
+    #[arg(long)]
+    pub workspaces_per_row: Option<u8>,
+    #[arg(long)]
+    pub size_factor: Option<f64>,
+    /// Template expanded per client, e.g. "{class}: {title} [{workspace}]"
+    #[arg(long)]
+    pub format: Option<String>,
+    /// Extra directories searched for an icon file named after the class, before falling
+    /// back to the GTK icon theme
+    #[arg(long, value_delimiter = ',')]
+    pub icon_dirs: Vec<PathBuf>,
+    /// Icon name used when neither the theme nor a desktop file yields one
+    #[arg(long)]
+    pub fallback_icon: Option<String>,
+    /// Escape `&`/`<`/`>` in titles so they render correctly as GTK markup
+    #[arg(long)]
+    pub html_escape: bool,
+}
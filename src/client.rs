@@ -0,0 +1,48 @@
+use std::io::{Read, Write};
+use std::os::unix::net::UnixStream;
+
+use anyhow::Context;
+use log::debug;
+
+use crate::{Command, Config, GuiConfig, IpcMessage};
+
+/// Also used by `daemon::spawn_socket_server` to bind the same path
+pub(crate) fn socket_path() -> std::path::PathBuf {
+    let runtime_dir = std::env::var("XDG_RUNTIME_DIR").unwrap_or_else(|_| "/tmp".to_string());
+    std::path::PathBuf::from(runtime_dir).join("hyprswitch.sock")
+}
+
+/// Returns whether the daemon's unix socket is currently accepting connections
+pub fn daemon_running() -> bool {
+    UnixStream::connect(socket_path()).is_ok()
+}
+
+fn send(message: &IpcMessage) -> anyhow::Result<String> {
+    let payload = serde_json::to_string(message).context("Failed to serialize IPC message")?;
+    let mut stream = UnixStream::connect(socket_path()).context("Failed to connect to daemon socket")?;
+    stream.write_all(payload.as_bytes()).context("Failed to write to daemon socket")?;
+    stream.shutdown(std::net::Shutdown::Write).context("Failed to shutdown write half")?;
+
+    let mut response = String::new();
+    stream.read_to_string(&mut response).context("Failed to read daemon response")?;
+    debug!("Daemon response: {response}");
+    if let Some(error) = response.strip_prefix("error: ") {
+        anyhow::bail!("Daemon reported an error: {error}");
+    }
+    Ok(response)
+}
+
+pub fn send_init_command(config: Config, gui_config: GuiConfig) -> anyhow::Result<()> {
+    send(&IpcMessage::Init { config, gui_config })?;
+    Ok(())
+}
+
+pub fn send_switch_command(command: Command) -> anyhow::Result<()> {
+    send(&IpcMessage::Dispatch(command))?;
+    Ok(())
+}
+
+pub fn send_close_daemon(kill: bool) -> anyhow::Result<()> {
+    send(&IpcMessage::Close { kill })?;
+    Ok(())
+}
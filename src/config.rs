@@ -0,0 +1,89 @@
+use std::path::PathBuf;
+
+use anyhow::Context;
+use log::debug;
+use serde::Deserialize;
+
+use crate::{SortMode, SwitchType};
+
+/// Mirrors `cli::SimpleConf`/`cli::GuiConf`, but every field is optional so a partially-filled
+/// `config.toml` falls back to built-in defaults for whatever it omits.
+#[derive(Debug, Deserialize, Default)]
+pub struct FileConfig {
+    pub dry_run: Option<bool>,
+    pub verbose: Option<u8>,
+    pub switch_type: Option<SwitchType>,
+    pub sort_mode: Option<SortMode>,
+    pub filter_same_class: Option<bool>,
+    pub filter_current_workspace: Option<bool>,
+    pub filter_current_monitor: Option<bool>,
+    pub ignore_workspaces: Option<bool>,
+    pub ignore_monitors: Option<bool>,
+    pub custom_css: Option<PathBuf>,
+    pub show_title: Option<bool>,
+    pub workspaces_per_row: Option<u8>,
+    pub size_factor: Option<f64>,
+    pub format: Option<String>,
+    pub icon_dirs: Option<Vec<PathBuf>>,
+    pub fallback_icon: Option<String>,
+    pub html_escape: Option<bool>,
+}
+
+const DEFAULT_CONFIG_TOML: &str = r#"# hyprswitch config.toml
+# Every key is optional; CLI flags override these, these override the built-in defaults.
+
+# dry_run = false
+# verbose = 0
+
+# switch_type = "client" # client | workspace | monitor
+# sort_mode = "default"  # default | recent
+# filter_same_class = false
+# filter_current_workspace = false
+# filter_current_monitor = false
+# ignore_workspaces = false
+# ignore_monitors = false
+
+# custom_css = "~/.config/hyprswitch/style.css"
+# show_title = false
+# workspaces_per_row = 5
+# size_factor = 7.0
+
+# format = "{class}: {title} [{workspace}]"
+# icon_dirs = ["~/.local/share/hyprswitch/icons"]
+# fallback_icon = "application-x-executable"
+# html_escape = false
+"#;
+
+/// `$XDG_CONFIG_HOME/hyprswitch`, falling back to `~/.config/hyprswitch`
+pub fn config_dir() -> PathBuf {
+    std::env::var("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .unwrap_or_else(|_| PathBuf::from(std::env::var("HOME").unwrap_or_default()).join(".config"))
+        .join("hyprswitch")
+}
+
+pub fn config_path() -> PathBuf {
+    config_dir().join("config.toml")
+}
+
+/// Reads and parses `config.toml`, returning an all-`None` `FileConfig` if it doesn't exist
+pub fn load() -> anyhow::Result<FileConfig> {
+    let path = config_path();
+    if !path.exists() {
+        debug!("No config.toml at {path:?}, using built-in defaults");
+        return Ok(FileConfig::default());
+    }
+    let content = std::fs::read_to_string(&path).with_context(|| format!("Failed to read {path:?}"))?;
+    toml::from_str(&content).with_context(|| format!("Failed to parse {path:?}"))
+}
+
+/// Writes a commented default config if none exists yet (`hyprswitch init --write-default-config`)
+pub fn write_default_config() -> anyhow::Result<()> {
+    let path = config_path();
+    if path.exists() {
+        anyhow::bail!("{path:?} already exists, not overwriting");
+    }
+    std::fs::create_dir_all(config_dir()).context("Failed to create config directory")?;
+    std::fs::write(&path, DEFAULT_CONFIG_TOML).with_context(|| format!("Failed to write {path:?}"))?;
+    Ok(())
+}
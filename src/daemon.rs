@@ -0,0 +1,233 @@
+use std::collections::{HashMap, VecDeque};
+use std::io::{Read, Write};
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use anyhow::Context;
+use gtk4::glib;
+use hyprland::event_listener::EventListener;
+use hyprland::shared::Address;
+use log::{debug, info, trace, warn};
+
+use crate::{gui, Command, Config, GuiConfig, IpcMessage};
+
+/// Daemon-wide state that persists across `Gui`/`Dispatch` invocations
+struct DaemonState {
+    /// Most-recently-focused clients first, deduped by address. Used by `SortMode::Recent`.
+    focus_history: VecDeque<Address>,
+    /// Clients that have raised `urgent` since they last gained focus, oldest first
+    urgent: VecDeque<Address>,
+    /// Set by the last `IpcMessage::Init`, used by `IpcMessage::Dispatch` to know how to collect
+    /// and order clients
+    config: Option<Config>,
+}
+
+impl DaemonState {
+    fn new() -> Self {
+        Self { focus_history: VecDeque::new(), urgent: VecDeque::new(), config: None }
+    }
+
+    fn mark_focused(&mut self, address: Address) {
+        self.focus_history.retain(|a| a != &address);
+        self.focus_history.push_front(address);
+        self.urgent.retain(|a| a != &address);
+    }
+
+    fn mark_urgent(&mut self, address: Address) {
+        if !self.urgent.contains(&address) {
+            self.urgent.push_back(address);
+        }
+    }
+
+    fn forget(&mut self, address: &Address) {
+        self.focus_history.retain(|a| a != address);
+        self.urgent.retain(|a| a != address);
+    }
+}
+
+static STATE: std::sync::OnceLock<Mutex<DaemonState>> = std::sync::OnceLock::new();
+/// Channel to the thread running the GTK main loop, set once `gui::run` starts. GTK widgets
+/// aren't `Send`, so this is the only way the socket-server thread can tell it what to show.
+static GUI_SENDER: std::sync::OnceLock<Mutex<glib::Sender<gui::GuiEvent>>> = std::sync::OnceLock::new();
+
+/// Returns the focus-history order (most recent first) for `SortMode::Recent`
+pub fn recent_order() -> Vec<Address> {
+    STATE.get()
+        .map(|s| s.lock().expect("daemon state lock poisoned").focus_history.iter().cloned().collect())
+        .unwrap_or_default()
+}
+
+/// Returns the currently-urgent clients, oldest-outstanding first
+pub fn urgent_clients() -> Vec<Address> {
+    STATE.get()
+        .map(|s| s.lock().expect("daemon state lock poisoned").urgent.iter().cloned().collect())
+        .unwrap_or_default()
+}
+
+/// Whether we're running inside the daemon process (i.e. `focus_history`/`urgent` are populated
+/// by live Hyprland events). `Simple`/`Menu` run out-of-process and never see this state.
+pub fn is_tracking() -> bool {
+    STATE.get().is_some()
+}
+
+/// Starts the daemon: the Hyprland event listener and the client socket run on their own
+/// background threads, and the GTK main loop (which needs a thread to itself) runs on the
+/// calling thread until the process exits.
+pub fn start_daemon(gui_config: GuiConfig) -> anyhow::Result<()> {
+    info!("Daemon starting with gui config: {gui_config:?}");
+    STATE.set(Mutex::new(DaemonState::new())).ok();
+
+    std::thread::spawn(|| {
+        if let Err(e) = run_event_listener() {
+            warn!("Hyprland event listener stopped: {e:?}");
+        }
+    });
+
+    spawn_socket_server().context("Failed to start daemon socket")?;
+
+    let (sender, receiver) = glib::MainContext::channel(glib::Priority::DEFAULT);
+    GUI_SENDER.set(Mutex::new(sender)).ok();
+    gui::run(receiver).context("Failed to run GUI")
+}
+
+fn run_event_listener() -> anyhow::Result<()> {
+    let mut listener = EventListener::new();
+
+    listener.add_active_window_change_handler(|data| {
+        if let Some(window) = data {
+            trace!("Focus changed to {}", window.address);
+            if let Some(state) = STATE.get() {
+                state.lock().expect("daemon state lock poisoned").mark_focused(window.address);
+            }
+        }
+    });
+
+    listener.add_urgent_state_handler(|address| {
+        trace!("Window raised urgent {address}");
+        if let Some(state) = STATE.get() {
+            state.lock().expect("daemon state lock poisoned").mark_urgent(address);
+        }
+    });
+
+    listener.add_window_close_handler(|address| {
+        trace!("Window closed {address}");
+        if let Some(state) = STATE.get() {
+            state.lock().expect("daemon state lock poisoned").forget(&address);
+        }
+    });
+
+    listener.start_listener().context("Failed to start Hyprland event listener")
+}
+
+/// Binds `client::socket_path` and hands each connection off to `handle_connection` on its own
+/// thread, so a slow launcher/GUI client can't stall other requests.
+fn spawn_socket_server() -> anyhow::Result<()> {
+    let path = crate::client::socket_path();
+    let _ = std::fs::remove_file(&path);
+    let listener = UnixListener::bind(&path).with_context(|| format!("Failed to bind daemon socket at {path:?}"))?;
+
+    std::thread::spawn(move || {
+        for stream in listener.incoming() {
+            match stream {
+                Ok(stream) => {
+                    std::thread::spawn(move || {
+                        if let Err(e) = handle_connection(stream) {
+                            warn!("Failed to handle client connection: {e:?}");
+                        }
+                    });
+                }
+                Err(e) => warn!("Failed to accept client connection: {e:?}"),
+            }
+        }
+    });
+    Ok(())
+}
+
+fn handle_connection(mut stream: UnixStream) -> anyhow::Result<()> {
+    let mut buf = String::new();
+    stream.read_to_string(&mut buf).context("Failed to read request")?;
+    let message: IpcMessage = serde_json::from_str(&buf).context("Failed to parse IPC message")?;
+    debug!("Received IPC message: {message:?}");
+
+    let response = match dispatch_message(message) {
+        Ok(()) => "ok".to_string(),
+        Err(e) => format!("error: {e:?}"),
+    };
+    stream.write_all(response.as_bytes()).context("Failed to write response")?;
+    Ok(())
+}
+
+fn dispatch_message(message: IpcMessage) -> anyhow::Result<()> {
+    match message {
+        IpcMessage::Init { config, gui_config } => {
+            if let Some(state) = STATE.get() {
+                state.lock().expect("daemon state lock poisoned").config = Some(config.clone());
+            }
+            show_gui(&config, gui_config, urgent_clients().into_iter().collect())
+        }
+        IpcMessage::Dispatch(command) => run_dispatch(command),
+        IpcMessage::Close { kill } => {
+            if let Some(sender) = GUI_SENDER.get() {
+                let _ = sender.lock().expect("gui sender lock poisoned").send(gui::GuiEvent::Hide);
+            }
+            deactivate_submap()?;
+            if kill {
+                std::process::exit(0);
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Collects clients using the config sent along with `IpcMessage::Init` and hands them (plus
+/// the current urgent set, so the GTK thread can mark them) to the GTK thread to render
+fn show_gui(config: &Config, gui_config: GuiConfig, urgent: std::collections::HashSet<Address>) -> anyhow::Result<()> {
+    let (clients_data, _active) = crate::handle::collect_data(config.clone())?;
+    let sender = GUI_SENDER.get().context("GUI not initialised")?.lock().expect("gui sender lock poisoned");
+    sender.send(gui::GuiEvent::Show { gui_config, clients_data, urgent }).context("Failed to notify GUI thread")
+}
+
+/// Runs a switch using the `Config` stored by the last `IpcMessage::Init` (or built-in defaults
+/// if `dispatch` is used before any `init`)
+fn run_dispatch(command: Command) -> anyhow::Result<()> {
+    let config = STATE.get()
+        .and_then(|s| s.lock().expect("daemon state lock poisoned").config.clone())
+        .unwrap_or_default();
+
+    let (clients_data, active) = crate::handle::collect_data(config.clone())?;
+    let active = crate::handle::resolve_active(&config.switch_type, active);
+    let next_active = crate::handle::find_next(&config.switch_type, command, &clients_data, &active)?;
+    crate::handle::switch_to_active(&next_active, &clients_data)
+}
+
+/// Resets the `hyprswitch` keybind submap, in case we exit while it is still active
+pub fn deactivate_submap() -> anyhow::Result<()> {
+    hyprland::ctl::Submap::call("reset".to_string()).context("Failed to reset submap")?;
+    Ok(())
+}
+
+/// Returns an empty map; no desktop files are read.
+pub fn get_desktop_files_debug() -> HashMap<String, (String, u8)> {
+    debug!("Desktop files are not scanned, returning no entries");
+    HashMap::new()
+}
+
+/// Returns `class` itself with source `0` (`Name`); no desktop file is read.
+pub fn get_icon_name_debug(class: &str) -> anyhow::Result<(String, u8)> {
+    Ok((class.to_string(), 0))
+}
+
+/// Searches `icon_dirs` (in order) for a file named `{class}.*`, used before falling back
+/// to the GTK icon theme / desktop-file lookup
+pub fn find_icon_in_dirs(class: &str, icon_dirs: &[PathBuf]) -> Option<PathBuf> {
+    for dir in icon_dirs {
+        let Ok(entries) = std::fs::read_dir(dir) else { continue };
+        for entry in entries.flatten() {
+            if entry.path().file_stem().is_some_and(|stem| stem == class) {
+                return Some(entry.path());
+            }
+        }
+    }
+    None
+}
@@ -0,0 +1,86 @@
+use std::collections::HashSet;
+
+use gtk4::prelude::*;
+use gtk4::{glib, Application, ApplicationWindow, Box as GtkBox, Image, Label, ListBox, ListBoxRow, Orientation};
+use hyprland::data::Client;
+use hyprland::shared::Address;
+
+use crate::handle::{format_client, ClientsData};
+use crate::GuiConfig;
+
+/// Told to the GTK thread by `daemon::handle_connection`, since GTK widgets aren't `Send` and
+/// can only be touched from the thread running the main loop
+pub enum GuiEvent {
+    Show { gui_config: GuiConfig, clients_data: ClientsData, urgent: HashSet<Address> },
+    Hide,
+}
+
+/// Runs the GTK main loop on the calling thread until the process exits. Meant to be the last
+/// thing `daemon::start_daemon` does, on its own (non-Hyprland-event-listener) thread.
+pub fn run(receiver: glib::Receiver<GuiEvent>) -> anyhow::Result<()> {
+    gtk4::init()?;
+    let app = Application::builder().application_id("io.github.h3rmt.hyprswitch").build();
+
+    app.connect_activate(move |app| {
+        let window = ApplicationWindow::builder().application(app).title("hyprswitch").default_width(600).build();
+        let list = ListBox::new();
+        window.set_child(Some(&list));
+
+        receiver.attach(None, glib::clone!(@weak window, @weak list => @default-return glib::ControlFlow::Break, move |event| {
+            match event {
+                GuiEvent::Show { gui_config, clients_data, urgent } => {
+                    show_clients(&window, &list, &gui_config, &clients_data, &urgent);
+                }
+                GuiEvent::Hide => window.set_visible(false),
+            }
+            glib::ControlFlow::Continue
+        }));
+    });
+
+    app.run();
+    Ok(())
+}
+
+/// Renders `gui_config.format`/`icon_dirs`/`fallback_icon`/`html_escape` for each client,
+/// marking `urgent` entries with the `urgent` CSS class, and applies `custom_css` if set
+fn show_clients(window: &ApplicationWindow, list: &ListBox, gui_config: &GuiConfig, clients_data: &ClientsData, urgent: &HashSet<Address>) {
+    if let Some(css) = &gui_config.custom_css {
+        let provider = gtk4::CssProvider::new();
+        provider.load_from_path(css);
+        if let Some(display) = gtk4::gdk::Display::default() {
+            gtk4::style_context_add_provider_for_display(&display, &provider, gtk4::STYLE_PROVIDER_PRIORITY_APPLICATION);
+        }
+    }
+
+    while let Some(row) = list.row_at_index(0) {
+        list.remove(&row);
+    }
+
+    for client in &clients_data.clients {
+        let row = GtkBox::new(Orientation::Horizontal, 6);
+        if let Some(icon) = resolve_icon(client, gui_config) {
+            row.append(&icon);
+        }
+        let label = Label::new(Some(&format_client(&gui_config.format, client, gui_config.html_escape)));
+        if urgent.contains(&client.address) {
+            label.add_css_class("urgent");
+        }
+        row.append(&label);
+        list.append(&ListBoxRow::builder().child(&row).build());
+    }
+
+    window.present();
+}
+
+/// Same precedence as `main.rs`'s `Icon` debug command: `icon_dirs` first, then the GTK icon
+/// theme, then `fallback_icon`
+fn resolve_icon(client: &Client, gui_config: &GuiConfig) -> Option<Image> {
+    if let Some(path) = crate::daemon::find_icon_in_dirs(&client.class, &gui_config.icon_dirs) {
+        return Some(Image::from_file(path));
+    }
+    let theme = gtk4::IconTheme::new();
+    if theme.has_icon(&client.class) {
+        return Some(Image::from_icon_name(&client.class));
+    }
+    gui_config.fallback_icon.as_deref().map(Image::from_icon_name)
+}
@@ -0,0 +1,234 @@
+use anyhow::Context;
+use hyprland::data::{Client, Clients};
+use hyprland::dispatch::{Dispatch, DispatchType, WindowIdentifier};
+use hyprland::shared::{Address, HyprData, MonitorId, WorkspaceId};
+use log::{debug, warn};
+
+use crate::{daemon, Active, Command, Config, SortMode, SwitchType};
+
+#[derive(Debug, Clone)]
+pub struct ClientsData {
+    pub clients: Vec<Client>,
+}
+
+/// Gathers the current client list (already ordered per `config.sort_mode`/filters) plus
+/// the currently active client/workspace/monitor
+pub fn collect_data(config: Config) -> anyhow::Result<(ClientsData, (Option<Address>, Option<WorkspaceId>, Option<MonitorId>))> {
+    let mut clients: Vec<Client> = Clients::get().context("Failed to get clients from Hyprland")?.to_vec();
+
+    if config.filter_same_class {
+        if let Some(active) = Client::get_active().context("Failed to get active client")?.as_ref() {
+            clients.retain(|c| c.class == active.class);
+        }
+    }
+    if config.filter_current_workspace {
+        if let Some(active) = Client::get_active().context("Failed to get active client")?.as_ref() {
+            clients.retain(|c| c.workspace.id == active.workspace.id);
+        }
+    }
+    if config.filter_current_monitor {
+        if let Some(active) = Client::get_active().context("Failed to get active client")?.as_ref() {
+            clients.retain(|c| c.monitor == active.monitor);
+        }
+    }
+
+    match config.sort_mode {
+        SortMode::Default => {}
+        SortMode::Recent => {
+            if !daemon::is_tracking() {
+                warn!("sort_mode=recent requested, but focus history is only tracked inside the daemon process; falling back to spatial order. Run this through `hyprswitch init`/`gui`/`dispatch` instead of `simple`/`menu`.");
+            }
+            let order = daemon::recent_order();
+            clients.sort_by_key(|c| order.iter().position(|a| a == &c.address).unwrap_or(usize::MAX));
+        }
+    }
+
+    let active = Client::get_active().context("Failed to get active client")?;
+    let active_address = active.as_ref().map(|c| c.address.clone());
+    let active_workspace = active.as_ref().map(|c| c.workspace.id);
+    let active_monitor = active.as_ref().map(|c| c.monitor);
+
+    Ok((ClientsData { clients }, (active_address, active_workspace, active_monitor)))
+}
+
+/// Resolves `collect_data`'s raw active-entity tuple into the `Active` variant relevant for
+/// `switch_type`. Shared by `Simple` and the daemon's socket-side `Dispatch` handling.
+pub fn resolve_active(switch_type: &SwitchType, active: (Option<Address>, Option<WorkspaceId>, Option<MonitorId>)) -> Active {
+    match switch_type {
+        SwitchType::Client => active.0.map(Active::Client).unwrap_or(Active::Unknown),
+        SwitchType::Workspace => active.1.map(Active::Workspace).unwrap_or(Active::Unknown),
+        SwitchType::Monitor => active.2.map(Active::Monitor).unwrap_or(Active::Unknown),
+    }
+}
+
+/// Advances `active` by `command.offset` steps (respecting `command.reverse`) through
+/// `clients_data`, wrapping around at the ends
+pub fn find_next(switch_type: &SwitchType, command: Command, clients_data: &ClientsData, active: &Active) -> anyhow::Result<Active> {
+    if command.to_urgent {
+        return jump_to_urgent(clients_data);
+    }
+
+    match switch_type {
+        SwitchType::Client => {
+            let urgent = daemon::urgent_clients();
+            if command.urgent_first && !daemon::is_tracking() {
+                warn!("--urgent-first requested, but urgent windows are only tracked inside the daemon process; falling back to the normal order. Run this through `hyprswitch init`/`gui`/`dispatch` instead of `simple`/`menu`.");
+            }
+            // `urgent` is oldest-outstanding-first; keep that order so cycling still visits the
+            // longest-waiting urgent client first.
+            let urgent_present: Vec<Address> = if command.urgent_first {
+                urgent.iter().filter(|a| clients_data.clients.iter().any(|c| &c.address == *a)).cloned().collect()
+            } else {
+                Vec::new()
+            };
+            let restricted = !urgent_present.is_empty();
+            let addresses: Vec<Address> = if restricted {
+                urgent_present
+            } else {
+                clients_data.clients.iter().map(|c| c.address.clone()).collect()
+            };
+            if addresses.is_empty() {
+                anyhow::bail!("No clients to switch between");
+            }
+            let current = match active {
+                Active::Client(addr) => addresses.iter().position(|a| a == addr),
+                _ => None,
+            };
+
+            let next = match current {
+                Some(pos) => wrap_index(pos, addresses.len(), command.offset, command.reverse),
+                // The active client isn't part of the restricted urgent-first list (it wasn't
+                // urgent itself) - land on the oldest outstanding urgent client directly instead
+                // of advancing `offset` steps from an assumed position 0, which would skip it.
+                None if restricted => 0,
+                None => wrap_index(0, addresses.len(), command.offset, command.reverse),
+            };
+            debug!("Switching from {current:?} to {next} of {} clients", addresses.len());
+            Ok(Active::Client(addresses[next].clone()))
+        }
+        SwitchType::Workspace | SwitchType::Monitor => {
+            anyhow::bail!("Switching by {switch_type:?} is not yet supported")
+        }
+    }
+}
+
+/// Advances `current` by `offset` steps (backwards if `reverse`) through a list of `len`
+/// items, wrapping around at the ends. `len` must be > 0.
+fn wrap_index(current: usize, len: usize, offset: u8, reverse: bool) -> usize {
+    let offset = offset as isize * if reverse { -1 } else { 1 };
+    (current as isize + offset).rem_euclid(len as isize) as usize
+}
+
+/// Jumps directly to the oldest client that is still flagged urgent (`dispatch --to-urgent`).
+/// `daemon::urgent_clients` is oldest-outstanding-first, so the first entry still present in
+/// `clients_data` is the one to use.
+pub fn jump_to_urgent(clients_data: &ClientsData) -> anyhow::Result<Active> {
+    if !daemon::is_tracking() {
+        warn!("--to-urgent requested, but urgent windows are only tracked inside the daemon process; run this through `hyprswitch init`/`gui`/`dispatch` instead of `simple`/`menu`.");
+    }
+    daemon::urgent_clients().into_iter()
+        .find(|addr| clients_data.clients.iter().any(|c| &c.address == addr))
+        .map(Active::Client)
+        .context("No urgent clients to switch to")
+}
+
+/// Expands a display template (e.g. `"{class}: {title} [{workspace}]"`) for one client.
+/// Shared by the `Menu` stdin-launcher lines and the GUI labels rendered by `gui::show_clients`.
+pub fn format_client(template: &str, client: &Client, html_escape: bool) -> String {
+    expand_template(template, &client.class, &client.title, client.workspace.id, html_escape)
+}
+
+fn expand_template(template: &str, class: &str, title: &str, workspace: WorkspaceId, html_escape: bool) -> String {
+    let (class, title) = if html_escape {
+        (html_escape_str(class), html_escape_str(title))
+    } else {
+        (class.to_string(), title.to_string())
+    };
+
+    template
+        .replace("{class}", &class)
+        .replace("{title}", &title)
+        .replace("{workspace}", &workspace.to_string())
+}
+
+fn html_escape_str(s: &str) -> String {
+    s.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+/// Focuses the client/workspace/monitor referred to by `active`
+pub fn switch_to_active(active: &Active, clients_data: &ClientsData) -> anyhow::Result<()> {
+    if crate::DRY.get().copied().unwrap_or(false) {
+        debug!("Dry run, not dispatching: {active:?}");
+        return Ok(());
+    }
+
+    match active {
+        Active::Client(address) => {
+            if !clients_data.clients.iter().any(|c| &c.address == address) {
+                anyhow::bail!("Client {address} is no longer present");
+            }
+            Dispatch::call(DispatchType::FocusWindow(WindowIdentifier::Address(address.clone())))
+                .with_context(|| format!("Failed to focus client {address}"))?;
+        }
+        Active::Workspace(id) => {
+            Dispatch::call(DispatchType::Workspace(hyprland::dispatch::WorkspaceIdentifierWithSpecial::Id(*id)))
+                .with_context(|| format!("Failed to focus workspace {id}"))?;
+        }
+        Active::Monitor(_) | Active::Unknown => {
+            anyhow::bail!("Nothing to switch to")
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{expand_template, html_escape_str, wrap_index};
+
+    #[test]
+    fn expands_all_placeholders() {
+        let result = expand_template("{class}: {title} [{workspace}]", "firefox", "Inbox", 3, false);
+        assert_eq!(result, "firefox: Inbox [3]");
+    }
+
+    #[test]
+    fn escapes_html_only_when_requested() {
+        let escaped = expand_template("{title}", "class", "A & <B>", 1, true);
+        assert_eq!(escaped, "A &amp; &lt;B&gt;");
+
+        let unescaped = expand_template("{title}", "class", "A & <B>", 1, false);
+        assert_eq!(unescaped, "A & <B>");
+    }
+
+    #[test]
+    fn html_escape_str_escapes_amp_lt_gt() {
+        assert_eq!(html_escape_str("<a & b>"), "&lt;a &amp; b&gt;");
+    }
+
+    #[test]
+    fn wraps_forward_past_the_end() {
+        assert_eq!(wrap_index(2, 3, 1, false), 0);
+    }
+
+    #[test]
+    fn wraps_backward_past_the_start() {
+        assert_eq!(wrap_index(0, 3, 1, true), 2);
+    }
+
+    #[test]
+    fn steps_within_bounds_without_wrapping() {
+        assert_eq!(wrap_index(0, 3, 1, false), 1);
+        assert_eq!(wrap_index(1, 3, 1, true), 0);
+    }
+
+    #[test]
+    fn offset_larger_than_len_wraps_multiple_times() {
+        assert_eq!(wrap_index(0, 3, 7, false), 1);
+    }
+
+    #[test]
+    fn single_client_always_stays_put() {
+        assert_eq!(wrap_index(0, 1, 1, false), 0);
+        assert_eq!(wrap_index(0, 1, 1, true), 0);
+    }
+}
@@ -0,0 +1,199 @@
+use std::sync::{Mutex, OnceLock};
+
+use anyhow::Context;
+use log::debug;
+
+pub mod cli;
+pub mod client;
+pub mod config;
+pub mod daemon;
+pub mod gui;
+pub mod handle;
+
+/// Whether the current invocation is a dry-run (no dispatch calls are actually sent to Hyprland)
+pub static DRY: OnceLock<bool> = OnceLock::new();
+/// Initialised to `false` by `main`; nothing in the crate reads it or sets it to `true`
+pub static ACTIVE: OnceLock<Mutex<bool>> = OnceLock::new();
+
+/// Which entity `find_next` advances over
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SwitchType {
+    #[default]
+    Client,
+    Workspace,
+    Monitor,
+}
+
+/// The currently focused entity, resolved by `collect_data`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Active {
+    Client(hyprland::shared::Address),
+    Workspace(hyprland::shared::WorkspaceId),
+    Monitor(hyprland::shared::MonitorId),
+    Unknown,
+}
+
+/// Direction (and modifiers) requested by the CLI/GUI, consumed by `handle::find_next`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct Command {
+    pub reverse: bool,
+    pub offset: u8,
+    /// Cycle through currently-urgent clients before falling back to the normal order
+    pub urgent_first: bool,
+    /// Jump directly to the oldest outstanding urgent client instead of cycling
+    pub to_urgent: bool,
+}
+
+impl From<cli::SimpleOpts> for Command {
+    fn from(opts: cli::SimpleOpts) -> Self {
+        Command {
+            reverse: opts.reverse,
+            offset: opts.offset,
+            urgent_first: opts.urgent_first,
+            to_urgent: false,
+        }
+    }
+}
+
+/// How `collect_data`/`find_next` order the collected clients before cycling
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SortMode {
+    /// Spatial/index order as reported by Hyprland (current behaviour)
+    #[default]
+    Default,
+    /// Most-recently-used: reverse order of last focus, maintained by the daemon
+    Recent,
+}
+
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+pub struct Config {
+    pub switch_type: SwitchType,
+    pub sort_mode: SortMode,
+    pub filter_same_class: bool,
+    pub filter_current_workspace: bool,
+    pub filter_current_monitor: bool,
+    pub ignore_workspaces: bool,
+    pub ignore_monitors: bool,
+}
+
+impl Config {
+    /// CLI flags > `config.toml` > built-in defaults
+    pub fn from_sources(cli: cli::SimpleConf, file: &config::FileConfig) -> Self {
+        Config {
+            switch_type: cli.switch_type.or(file.switch_type).unwrap_or(SwitchType::Client),
+            sort_mode: cli.sort_mode.or(file.sort_mode).unwrap_or_default(),
+            filter_same_class: cli.filter_same_class || file.filter_same_class.unwrap_or(false),
+            filter_current_workspace: cli.filter_current_workspace || file.filter_current_workspace.unwrap_or(false),
+            filter_current_monitor: cli.filter_current_monitor || file.filter_current_monitor.unwrap_or(false),
+            ignore_workspaces: cli.ignore_workspaces || file.ignore_workspaces.unwrap_or(false),
+            ignore_monitors: cli.ignore_monitors || file.ignore_monitors.unwrap_or(false),
+        }
+    }
+}
+
+/// Default per-client display template for the GUI, used when neither CLI nor `config.toml`
+/// sets `format`
+pub const DEFAULT_FORMAT: &str = "{title}";
+
+/// Default per-client display template for `Menu`'s launcher lines. Unlike the GUI (which has
+/// room for an icon next to the title), a flat launcher list needs class/workspace too or
+/// same-titled windows become indistinguishable.
+pub const DEFAULT_MENU_FORMAT: &str = "{class}: {title} [{workspace}]";
+
+/// Sent to the daemon (which owns the actual GTK window, see `gui::run`) via `IpcMessage::Init`.
+/// `format`/`icon_dirs`/`fallback_icon`/`html_escape` are rendered per-row by
+/// `gui::show_clients`; `workspaces_per_row`/`size_factor`/`show_title` still only describe a
+/// spatial workspace grid that doesn't exist yet, so the window is a plain list until that
+/// layout is built.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct GuiConfig {
+    pub custom_css: Option<std::path::PathBuf>,
+    pub show_title: bool,
+    pub workspaces_per_row: u8,
+    pub size_factor: f64,
+    pub format: String,
+    pub icon_dirs: Vec<std::path::PathBuf>,
+    pub fallback_icon: Option<String>,
+    pub html_escape: bool,
+}
+
+impl GuiConfig {
+    /// CLI flags > `config.toml` > built-in defaults
+    pub fn from_sources(cli: cli::GuiConf, file: &config::FileConfig) -> Self {
+        GuiConfig {
+            custom_css: cli.custom_css.or_else(|| file.custom_css.clone()),
+            show_title: cli.show_title || file.show_title.unwrap_or(false),
+            workspaces_per_row: cli.workspaces_per_row.or(file.workspaces_per_row).unwrap_or(5),
+            size_factor: cli.size_factor.or(file.size_factor).unwrap_or(7.0),
+            format: cli.format.or_else(|| file.format.clone()).unwrap_or_else(|| DEFAULT_FORMAT.to_string()),
+            icon_dirs: if !cli.icon_dirs.is_empty() { cli.icon_dirs } else { file.icon_dirs.clone().unwrap_or_default() },
+            fallback_icon: cli.fallback_icon.or_else(|| file.fallback_icon.clone()),
+            html_escape: cli.html_escape || file.html_escape.unwrap_or(false),
+        }
+    }
+}
+
+/// Wire format spoken over the daemon's unix socket (`client::send_*` -> `daemon::handle_connection`)
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub enum IpcMessage {
+    /// Sent by `Gui`: store the resolved config and show the client list
+    Init { config: Config, gui_config: GuiConfig },
+    /// Sent by `Dispatch`: run a switch using the config stored by the last `Init`
+    Dispatch(Command),
+    /// Sent by `Close`: hide the GUI and, if `kill`, terminate the daemon immediately
+    Close { kill: bool },
+}
+
+/// Fetches the running Hyprland version and logs it at debug level. The version is not compared
+/// against any minimum; an error is returned only if it cannot be fetched.
+pub fn check_version() -> anyhow::Result<()> {
+    let version = hyprland::data::Version::get().context("Failed to get Hyprland version")?;
+    debug!("Hyprland version: {version:?}");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cli_flag_overrides_file_value() {
+        let cli = cli::SimpleConf { switch_type: Some(SwitchType::Workspace), ..Default::default() };
+        let file = config::FileConfig { switch_type: Some(SwitchType::Monitor), ..Default::default() };
+        assert_eq!(Config::from_sources(cli, &file).switch_type, SwitchType::Workspace);
+    }
+
+    #[test]
+    fn file_value_used_when_cli_unset() {
+        let cli = cli::SimpleConf::default();
+        let file = config::FileConfig { sort_mode: Some(SortMode::Recent), ..Default::default() };
+        assert_eq!(Config::from_sources(cli, &file).sort_mode, SortMode::Recent);
+    }
+
+    #[test]
+    fn built_in_default_used_when_neither_set() {
+        let config = Config::from_sources(cli::SimpleConf::default(), &config::FileConfig::default());
+        assert_eq!(config.switch_type, SwitchType::Client);
+        assert_eq!(config.sort_mode, SortMode::Default);
+    }
+
+    #[test]
+    fn bool_flags_are_additive_not_overriding() {
+        // a `true` in config.toml can't be turned back off by the absence of a CLI flag
+        let cli = cli::SimpleConf::default();
+        let file = config::FileConfig { filter_same_class: Some(true), ..Default::default() };
+        assert!(Config::from_sources(cli, &file).filter_same_class);
+    }
+
+    #[test]
+    fn gui_config_precedence() {
+        let cli = cli::GuiConf { size_factor: Some(3.0), ..Default::default() };
+        let file = config::FileConfig { size_factor: Some(9.0), workspaces_per_row: Some(8), ..Default::default() };
+        let gui_config = GuiConfig::from_sources(cli, &file);
+        assert_eq!(gui_config.size_factor, 3.0); // CLI wins
+        assert_eq!(gui_config.workspaces_per_row, 8); // file wins (CLI unset)
+        assert_eq!(gui_config.format, DEFAULT_FORMAT); // built-in default (neither set)
+    }
+}
@@ -1,15 +1,16 @@
 use std::error::Error;
+use std::io::Write;
 use std::process::exit;
 use std::sync::Mutex;
 
 use anyhow::Context;
 use clap::Parser;
 use gtk4::IconTheme;
-use hyprswitch::cli::{App, SwitchType};
+use hyprswitch::cli::App;
 use hyprswitch::client::{daemon_running, send_close_daemon, send_init_command, send_switch_command};
-use hyprswitch::daemon::{deactivate_submap, get_desktop_files_debug, get_icon_name_debug, start_daemon};
-use hyprswitch::handle::{collect_data, find_next, switch_to_active};
-use hyprswitch::{check_version, cli, Active, Command, Config, GuiConfig, ACTIVE, DRY};
+use hyprswitch::daemon::{deactivate_submap, find_icon_in_dirs, get_desktop_files_debug, get_icon_name_debug, start_daemon};
+use hyprswitch::handle::{collect_data, find_next, format_client, resolve_active, switch_to_active};
+use hyprswitch::{check_version, cli, config, Active, Command, Config, GuiConfig, ACTIVE, DEFAULT_MENU_FORMAT, DRY};
 use log::{debug, info, trace, warn};
 use notify_rust::{Notification, Urgency};
 
@@ -34,7 +35,14 @@ fn main() -> Result<(), Box<dyn Error>> {
             eprintln!("{}", e);
             exit(1);
         });
-    stderrlog::new().module(module_path!()).verbosity(cli.global_opts.verbose as usize + 1).init()
+    // Loaded before the logger so verbosity from config.toml can be merged with -v/-vv/-vvv
+    let file_config = config::load().unwrap_or_else(|e| {
+        eprintln!("Failed to load config.toml, using built-in defaults: {e:?}");
+        config::FileConfig::default()
+    });
+
+    let verbosity = cli.global_opts.verbose.max(file_config.verbose.unwrap_or(0));
+    stderrlog::new().module(module_path!()).verbosity(verbosity as usize + 1).init()
         .context("Failed to initialize logging :(").unwrap_or_else(|e| warn!("{:?}", e));
 
     let _ = check_version().map_err(|e| {
@@ -42,17 +50,23 @@ fn main() -> Result<(), Box<dyn Error>> {
         debug!("{:?}", e);
     });
 
-    DRY.set(cli.global_opts.dry_run).expect("unable to set DRY (already filled???)");
+    DRY.set(cli.global_opts.dry_run || file_config.dry_run.unwrap_or(false)).expect("unable to set DRY (already filled???)");
     ACTIVE.set(Mutex::new(false)).expect("unable to set ACTIVE (already filled???)");
 
     match cli.command {
-        cli::Command::Init { custom_css, show_title, workspaces_per_row, size_factor } => {
+        cli::Command::Init { gui_conf, write_default_config } => {
+            if write_default_config {
+                config::write_default_config().context("Failed to write default config.toml")?;
+                info!("Wrote default config to {:?}", config::config_path());
+                return Ok(());
+            }
             if daemon_running() {
                 warn!("Daemon already running");
                 return Ok(());
             }
             info!("Starting daemon");
-            start_daemon(custom_css, show_title, size_factor, workspaces_per_row)
+            let gui_config = GuiConfig::from_sources(gui_conf, &file_config);
+            start_daemon(gui_config)
                 .context("Failed to run daemon")
                 .inspect_err(|_| {
                     let _ = deactivate_submap();
@@ -68,8 +82,8 @@ fn main() -> Result<(), Box<dyn Error>> {
             }
             send_close_daemon(kill).context("Failed to send kill command to daemon")?;
         }
-        cli::Command::Dispatch { simple_opts } => {
-            let command = Command::from(simple_opts);
+        cli::Command::Dispatch { simple_opts, to_urgent } => {
+            let command = Command { to_urgent, ..Command::from(simple_opts) };
             send_switch_command(command)
                 .with_context(|| format!("Failed to send switch command with command {command:?} to daemon"))?;
         }
@@ -86,32 +100,64 @@ fn main() -> Result<(), Box<dyn Error>> {
 
             // Daemon is not running
             info!("initialising daemon");
-            let config = Config::from(simple_config);
-            let gui_config = GuiConfig::from(gui_conf);
+            let config = Config::from_sources(simple_config, &file_config);
+            let gui_config = GuiConfig::from_sources(gui_conf, &file_config);
             send_init_command(config.clone(), gui_config.clone())
                 .with_context(|| format!("Failed to send init command with config {config:?} and gui_config {gui_config:?} to daemon"))?;
 
             return Ok(());
         }
         cli::Command::Simple { simple_opts, simple_conf } => {
-            let config = Config::from(simple_conf);
+            let config = Config::from_sources(simple_conf, &file_config);
             let (clients_data, active) = collect_data(config.clone()).with_context(|| format!("Failed to collect data with config {config:?}"))?;
             trace!("Clients data: {:?}", clients_data);
 
             let command = Command::from(simple_opts);
 
-            let active = match config.switch_type {
-                SwitchType::Client => if let Some(add) = active.0 { Active::Client(add) } else { Active::Unknown },
-                SwitchType::Workspace => if let Some(ws) = active.1 { Active::Workspace(ws) } else { Active::Unknown },
-                SwitchType::Monitor => if let Some(mon) = active.2 { Active::Monitor(mon) } else { Active::Unknown },
-            };
+            let active = resolve_active(&config.switch_type, active);
             info!("Active: {:?}", active);
             let next_active = find_next(&config.switch_type, command, &clients_data, &active);
             if let Ok(next_active) = next_active {
                 switch_to_active(&next_active, &clients_data)?;
             }
         }
-        cli::Command::Icon { class, desktop_files, list } => {
+        cli::Command::Menu { simple_conf, gui_conf, launcher } => {
+            let config = Config::from_sources(simple_conf, &file_config);
+            let (clients_data, _active) = collect_data(config.clone()).with_context(|| format!("Failed to collect data with config {config:?}"))?;
+
+            // Menu gets its own default (class+title+workspace) rather than GuiConfig's
+            // `{title}`-only default, which on-screen GUI labels can get away with but a flat
+            // launcher list can't - same-titled windows would be indistinguishable.
+            let format = gui_conf.format.clone().or_else(|| file_config.format.clone()).unwrap_or_else(|| DEFAULT_MENU_FORMAT.to_string());
+            let html_escape = gui_conf.html_escape || file_config.html_escape.unwrap_or(false);
+
+            let lines: String = clients_data.clients.iter().enumerate()
+                .map(|(i, c)| format!("{i}\t{}\n", format_client(&format, c, html_escape)))
+                .collect();
+
+            let args = shell_words::split(&launcher).context("Failed to parse --launcher into arguments")?;
+            let (program, rest) = args.split_first().context("--launcher must not be empty")?;
+            let mut child = std::process::Command::new(program)
+                .args(rest)
+                .stdin(std::process::Stdio::piped())
+                .stdout(std::process::Stdio::piped())
+                .spawn()
+                .with_context(|| format!("Failed to spawn launcher {launcher}"))?;
+
+            child.stdin.take().context("Failed to open launcher stdin")?.write_all(lines.as_bytes())?;
+            let output = child.wait_with_output().context("Failed to wait for launcher")?;
+            let selection = String::from_utf8_lossy(&output.stdout);
+            if selection.trim().is_empty() {
+                info!("Launcher returned no selection (cancelled), doing nothing");
+                return Ok(());
+            }
+            let index: usize = selection.split('\t').next().unwrap_or("").trim().parse()
+                .with_context(|| format!("Launcher returned an unparseable selection: {selection:?}"))?;
+
+            let client = clients_data.clients.get(index).with_context(|| format!("Launcher selected out-of-range index {index}"))?;
+            switch_to_active(&Active::Client(client.address.clone()), &clients_data)?;
+        }
+        cli::Command::Icon { class, desktop_files, list, icon_dirs, fallback_icon } => {
             println!("use with -vvv icon ... to see full logs!");
             match (list, desktop_files) {
                 (true, false) => {
@@ -135,6 +181,10 @@ fn main() -> Result<(), Box<dyn Error>> {
                 }
                 _ => {
                     info!("[ICON] Icon for class {class}");
+                    if let Some(path) = find_icon_in_dirs(&class, &icon_dirs) {
+                        info!("[ICON] Satisfied by icon_dir entry {path:?}");
+                        return Ok(());
+                    }
                     gtk4::init().context("Failed to init gtk")?;
                     let theme = IconTheme::new();
                     if theme.has_icon(&class) {
@@ -151,8 +201,10 @@ fn main() -> Result<(), Box<dyn Error>> {
                         });
                         if theme.has_icon(&name.0) {
                             info!("[ICON] Theme contains icon for name {}", name.0);
+                        } else if let Some(fallback) = fallback_icon {
+                            info!("[ICON] Theme does not contain icon for name {}, satisfied by fallback_icon {fallback}", name.0);
                         } else {
-                            info!("[ICON] Theme does not contain icon for name {}", name.0);
+                            info!("[ICON] Theme does not contain icon for name {}, no fallback_icon configured", name.0);
                         }
                     }
                 }